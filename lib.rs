@@ -2,7 +2,7 @@
 
 /// This contract demonstrates a safe approach to handling randomness in smart contracts using an external oracle.
 /// It simulates a simple casino-like game where users can place bets and potentially win rewards.
-/// 
+///
 /// The provided code is for educational purposes only and should not be used in production.
 ///
 /// Key Features:
@@ -10,26 +10,68 @@
 /// - Integration with a Randomness Oracle: Relies on an external oracle (DIA Oracle in this example) to provide
 ///   unpredictable randomness, ensuring fairness.
 /// - Two-Phase Betting:
-///   - register_bet: User registers a bet and pays a fee.
+///   - register_bet: User picks a number `n` in `[0, 100]`, stakes a value, and pays a fee.
 ///   - resolve_bet: User resolves the bet based on the oracle's randomness to determine win/loss and receive rewards.
+/// - Proportional Payout: The higher the chosen `n`, the riskier the bet and the larger the reward.
+/// - Asynchronous Resolution: As an alternative to polling `resolve_bet`, a bet can be subscribed via
+///   `request_randomness`; the oracle then settles it itself through `fulfill_randomness`, VRFCoordinatorV2-style.
+/// - Optional Commit-Reveal: A bet may commit to `hash(secret)` up front and reveal `secret` at resolution,
+///   mixing it into the oracle's randomness so neither the oracle operator nor the player alone controls
+///   the outcome.
+/// - Prediction Markets: Beyond the fixed house game, anyone can create a multi-outcome market that pays
+///   winners pro-rata from the pooled stakes, minus the creator's rake.
+/// - VRF Proof Verification: `resolve_bet` only trusts the oracle's randomness once its VRF proof has been
+///   verified against the oracle's public key, set in the constructor.
 ///
 /// Contract Structure:
 ///
 /// Storage:
-/// bets: Mapping that stores bet details (bet id, round, user).
+/// bets: Mapping that stores bet details (round, user, chosen number, staked amount).
 /// oracle: Contract reference to the DIA Oracle.
+/// pending_requests: Mapping of outstanding randomness requests awaiting fulfillment by the oracle.
+/// markets: Mapping of prediction markets created via `create_market`.
+/// outcome_pools: Mapping of per-market, per-outcome staked totals.
+/// user_stakes: Mapping of per-market, per-user, per-outcome staked amounts.
+/// oracle_public_key: The oracle's VRF public key, used by `verify_proof`.
+/// proofs: Mapping of VRF proofs the oracle submitted per round.
 ///
 /// Functionality:
 ///
 /// - get_random(key: u64) -> Option<Vec<u8>>: Fetches randomness for a given round from the oracle.
-/// - register_bet() -> Result<(), Error>: Registers a new bet, assigning a future round number, and charging a fee.
-/// - resolve_bet(bet_id: BetId) -> Result<(), Error>: Resolves a bet based on the oracle's randomness,
-///   distributing rewards if applicable.
+/// - register_bet(n: u8, commitment: Option<Hash>) -> Result<(), Error>: Registers a new bet on number `n`,
+///   assigning a future round number, charging a fee, and optionally committing to a hashed secret.
+/// - resolve_bet(bet_id: BetId, secret: Option<Vec<u8>>) -> Result<(), Error>: Resolves a bet based on the
+///   oracle's randomness (mixed with a revealed `secret`, if committed), distributing rewards if applicable.
+/// - request_randomness(bet_id: BetId, num_confirmations: u16, callback_gas_limit: u32) -> Result<RequestId, Error>:
+///   Subscribes a registered bet to be settled asynchronously by the oracle.
+/// - fulfill_randomness(request_id: RequestId, randomness: Vec<u8>, proof: Vec<u8>) -> Result<(), Error>:
+///   Permissioned callback, invoked by the oracle, that verifies `proof` and settles the bet behind
+///   `request_id`.
+/// - create_market(resolution_round: Round, outcomes: u8, rake_bps: u16) -> Result<MarketId, Error>: Creates a
+///   multi-outcome prediction market.
+/// - place_bet(market_id: MarketId, outcome: u8) -> Result<(), Error>: Stakes the transferred value on an
+///   outcome of a market.
+/// - resolve_market(market_id: MarketId) -> Result<(), Error>: Picks the winning outcome from the oracle's
+///   randomness at `resolution_round`.
+/// - claim(market_id: MarketId) -> Result<(), Error>: Pays a winner their pro-rata share of the pool.
+/// - claim_rake(market_id: MarketId) -> Result<(), Error>: Pays the market creator their rake, once.
+/// - submit_proof(round: Round, proof: Vec<u8>) -> Result<(), Error>: Permissioned entry point for the oracle
+///   to submit the VRF proof for a round's randomness.
+/// - get_random_with_proof(round: Round) -> Option<(Vec<u8>, Vec<u8>)>: Fetches a round's randomness together
+///   with its submitted VRF proof.
 ///
 /// - get_id() -> BetId: Generates a unique bet identifier.
 /// - pay_fee(user: User) -> Result<(), Error>: Handles fee payment.
-/// - is_victorious(randomness: Vec<u8>) -> bool: Determines if the bet is a win based on randomness.
-/// - pay_reward(user: User) -> Result<(), Error>: Pays out rewards to the user.
+/// - reveal_randomness(bet_id, details, secret, randomness) -> Result<Vec<u8>, Error>: Verifies a revealed
+///   secret against its commitment and mixes it into the oracle's randomness.
+/// - settle_bet(details: &BetDetails, randomness: &[u8]) -> Result<(), Error>: Shared payout logic used by
+///   both `resolve_bet` and `fulfill_randomness`.
+/// - pay_reward(user: User, amount: Balance) -> Result<(), Error>: Pays out rewards to the user.
+/// - verify_proof(round: Round, randomness: &[u8], proof: &[u8]) -> bool: Verifies a VRF proof against the
+///   oracle's public key.
+/// - reduce_to_range(randomness: &[u8], bound: u64) -> u64: Unbiased reduction of oracle randomness
+///   to a value in `[0, bound)` via rejection sampling, used to derive the resolved number `r`.
+/// - hash_secret(secret: &[u8]) -> Hash: Hashes a commit-reveal secret for commitment verification.
 
 #[ink::contract]
 mod casino {
@@ -43,6 +85,24 @@ mod casino {
     pub enum Error {
         FailedTransfer,
         BetResolutionTooEarly,
+        InvalidBetNumber,
+        Unauthorized,
+        BetNotFound,
+        RequestNotFound,
+        InsufficientConfirmations,
+        MissingSecret,
+        CommitmentMismatch,
+        CommittedBetRequiresReveal,
+        MarketNotFound,
+        InvalidMarketParameters,
+        InvalidOutcome,
+        MarketAlreadyResolved,
+        BettingClosed,
+        MarketNotResolved,
+        MarketResolutionTooEarly,
+        NothingToClaim,
+        RakeAlreadyClaimed,
+        InvalidProof,
         Custom(Vec<u8>),
     }
 
@@ -50,21 +110,98 @@ mod casino {
     pub type BetId = u128;
     pub type Round = u64;
     pub type User = AccountId;
-    pub type BetDetails = (Round, User);
+    pub type RequestId = u128;
+    pub type MarketId = u128;
+
+    /// The minimum number of confirmations a caller may request before the
+    /// oracle is allowed to fulfill the randomness request.
+    pub const MIN_CONFIRMATIONS: u16 = 1;
+
+    /// `rake_bps` is expressed in basis points out of this denominator (100%).
+    pub const MAX_RAKE_BPS: u16 = 10_000;
+
+    /// A user-created prediction market: stakers back one of `outcomes` mutually
+    /// exclusive outcomes, and at `resolution_round` the oracle randomness
+    /// (reduced to `[0, outcomes)`) picks the winner. Winners split the total
+    /// pool, minus the creator's `rake_bps`, pro-rata to their stake.
+    #[derive(Clone, Debug, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Market {
+        creator: AccountId,
+        resolution_round: Round,
+        outcomes: u8,
+        rake_bps: u16,
+        total_pool: Balance,
+        winning_outcome: Option<u8>,
+        rake_claimed: bool,
+    }
+
+    /// A randomness request awaiting fulfillment, modeled on Chainlink's
+    /// VRFCoordinatorV2 subscription flow: a bet is registered for a request,
+    /// and the oracle (or a keeper acting on its behalf) settles it once
+    /// `ready_at` has passed.
+    #[derive(Clone, Debug, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct PendingRequest {
+        bet_id: BetId,
+        ready_at: BlockNumber,
+    }
+
+    /// Details of a bet registered by a user.
+    ///
+    /// `n` is the player-chosen number in `[0, 100]` and `stake` is the value
+    /// transferred along with `register_bet`. The payout is proportional to `n`:
+    /// the bet wins whenever the resolved number `r` is `>= n`, paying back
+    /// `stake + stake * n / 100`.
+    ///
+    /// `commitment`, if set, is a `hash(secret)` the user commits to when
+    /// registering the bet; the matching `secret` must be revealed when resolving
+    /// so the final randomness mixes in entropy the oracle never saw in advance.
+    #[derive(Clone, Debug, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct BetDetails {
+        round: Round,
+        user: User,
+        n: u8,
+        stake: Balance,
+        commitment: Option<Hash>,
+    }
 
     #[ink(storage)]
     pub struct Casino {
         bets: Mapping<BetId, BetDetails>,
         // reference to the oracle contract
         oracle: contract_ref!(RandomOracleGetter),
+        // pending randomness requests awaiting fulfillment by the oracle
+        pending_requests: Mapping<RequestId, PendingRequest>,
+        next_request_id: RequestId,
+        next_bet_id: BetId,
+        // prediction markets and their per-outcome, per-user stakes
+        markets: Mapping<MarketId, Market>,
+        outcome_pools: Mapping<(MarketId, u8), Balance>,
+        user_stakes: Mapping<(MarketId, AccountId, u8), Balance>,
+        next_market_id: MarketId,
+        // the oracle's VRF public key, used to verify proofs in `verify_proof`
+        oracle_public_key: [u8; 33],
+        // VRF proofs submitted by the oracle alongside a round's randomness
+        proofs: Mapping<Round, Vec<u8>>,
     }
 
     impl Casino {
         #[ink(constructor)]
-        pub fn new(oracle_address: AccountId) -> Self {
+        pub fn new(oracle_address: AccountId, oracle_public_key: [u8; 33]) -> Self {
             Self {
                 bets: Mapping::default(),
                 oracle: oracle_address.into(),
+                pending_requests: Mapping::default(),
+                next_request_id: 0,
+                next_bet_id: 0,
+                markets: Mapping::default(),
+                outcome_pools: Mapping::default(),
+                user_stakes: Mapping::default(),
+                next_market_id: 0,
+                oracle_public_key,
+                proofs: Mapping::default(),
             }
         }
 
@@ -74,40 +211,109 @@ mod casino {
             self.oracle.get_random_value_for_round(key)
         }
 
-        /// A user will call the message to place a bet
+        /// Permissioned entry point for the oracle to submit the VRF proof
+        /// backing the randomness it published for `round`.
+        #[ink(message)]
+        pub fn submit_proof(&mut self, round: Round, proof: Vec<u8>) -> Result<(), Error> {
+            if self.env().caller() != *self.oracle.as_ref() {
+                return Err(Error::Unauthorized);
+            }
+            self.proofs.insert(round, &proof);
+            Ok(())
+        }
+
+        /// Gets a round's randomness together with the VRF proof the oracle
+        /// submitted for it via [`Self::submit_proof`], if any.
         #[ink(message)]
-        pub fn register_bet(&mut self) -> Result<(), Error> {
+        pub fn get_random_with_proof(&self, round: Round) -> Option<(Vec<u8>, Vec<u8>)> {
+            let randomness = self.oracle.get_random_value_for_round(round)?;
+            let proof = self.proofs.get(round)?;
+            Some((randomness, proof))
+        }
+
+        /// Verifies that `proof` is a valid VRF proof, by the oracle's public key,
+        /// that `randomness` is the correct output for `round`.
+        ///
+        /// `proof` is expected to be a 65-byte ECDSA signature, recoverable to the
+        /// stored `oracle_public_key`, over `hash(round ++ randomness)`.
+        fn verify_proof(&self, round: Round, randomness: &[u8], proof: &[u8]) -> bool {
+            let Ok(signature) = <[u8; 65]>::try_from(proof) else {
+                return false;
+            };
+
+            let mut message = Vec::with_capacity(8 + randomness.len());
+            message.extend_from_slice(&round.to_be_bytes());
+            message.extend_from_slice(randomness);
+            let mut message_hash = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&message, &mut message_hash);
+
+            let mut recovered_key = [0u8; 33];
+            self.env()
+                .ecdsa_recover(&signature, &message_hash, &mut recovered_key)
+                .is_ok()
+                && recovered_key == self.oracle_public_key
+        }
+
+        /// A user will call the message to place a bet on number `n` (`0..=100`).
+        ///
+        /// The payout is proportional to `n`: a higher `n` is riskier (it wins less
+        /// often) but pays a larger reward when it does.
+        ///
+        /// `commitment` is an optional `hash(secret)`; if supplied, the matching
+        /// `secret` must be revealed to [`Self::resolve_bet`] so the oracle's
+        /// randomness is mixed with entropy only the player knew in advance.
+        #[ink(message, payable)]
+        pub fn register_bet(&mut self, n: u8, commitment: Option<Hash>) -> Result<(), Error> {
+            if n > 100 {
+                return Err(Error::InvalidBetNumber);
+            }
+
             let user = self.env().caller();
+            let stake = self.env().transferred_value();
             // The player pays the fee to the Casino for playing.
             self.pay_fee(user)?;
 
             let bet_id = self.get_id(); // get a fresh BetId
             let current_round = self.oracle.get_latest_round();
             let round = current_round + 2; // we need to make sure this is a round in the future;
-            let details = (round, user);
+            let details = BetDetails {
+                round,
+                user,
+                n,
+                stake,
+                commitment,
+            };
             self.bets.insert(bet_id, &details);
 
             Ok(())
         }
 
-        /// Depending on the randomness, provided by the oracle, determines if a user is victorious and pays 
-        /// them up in that case. 
-        /// 
+        /// Depending on the randomness, provided by the oracle, determines if a user is victorious and pays
+        /// them up in that case.
+        ///
         /// The user needs to wait a couple of blocks after registering a bet before calling this message.
+        /// If the bet was registered with a `commitment`, the matching `secret` must be passed here; it is
+        /// mixed into the oracle's randomness so that neither the oracle nor the player alone controls the
+        /// outcome. A missing or mismatched `secret` aborts resolution without settling the bet.
+        ///
+        /// The oracle's randomness is only trusted once its VRF proof verifies against the oracle's public
+        /// key; resolution errors out rather than paying if the proof is missing or invalid.
         #[ink(message)]
-        pub fn resolve_bet(&mut self, bet_id: BetId) -> Result<(), Error> {
-            let user = self.env().caller();
-            let round = self.bets.get(bet_id).unwrap().0;
-            let randomness = self.oracle.get_random_value_for_round(round);
+        pub fn resolve_bet(&mut self, bet_id: BetId, secret: Option<Vec<u8>>) -> Result<(), Error> {
+            let details = self.bets.get(bet_id).ok_or(Error::BetNotFound)?;
+            let randomness_and_proof = self.get_random_with_proof(details.round);
             // Based on `randomness` determine if the bet was won or lost. Pay out rewards to the user, etc.
-            match randomness {
-                Some(randomness) => {
-                    if self.is_victorious(randomness) {
-                        self.pay_reward(user)?;
+            match randomness_and_proof {
+                Some((randomness, proof)) => {
+                    if !self.verify_proof(details.round, &randomness, &proof) {
+                        return Err(Error::InvalidProof);
                     }
+                    let randomness = self.reveal_randomness(bet_id, &details, secret, randomness)?;
+                    self.settle_bet(&details, &randomness)?
                 },
                 None => {
-                    // After registering bet, user would need to wait a couple of blocks for randomness
+                    // After registering bet, user would need to wait a couple of blocks for randomness,
+                    // or the oracle has not yet submitted the VRF proof for this round.
                     return Err(Error::BetResolutionTooEarly);
                 }
             }
@@ -117,9 +323,283 @@ mod casino {
             Ok(())
         }
 
-        fn get_id(&self) -> BetId {
-            // implement id generation
-            42
+        /// Subscribes an already-registered bet to the oracle's asynchronous
+        /// randomness flow, modeled on `VRFCoordinatorV2::requestRandomWords`.
+        ///
+        /// Instead of the user polling [`Self::resolve_bet`] until enough blocks
+        /// have passed, the oracle (or a keeper acting on its behalf) settles the
+        /// bet itself by calling [`Self::fulfill_randomness`] once
+        /// `num_confirmations` blocks have elapsed.
+        #[ink(message)]
+        pub fn request_randomness(
+            &mut self,
+            bet_id: BetId,
+            num_confirmations: u16,
+            callback_gas_limit: u32,
+        ) -> Result<RequestId, Error> {
+            let _ = callback_gas_limit; // reserved for the off-chain keeper's callback budget
+            if num_confirmations < MIN_CONFIRMATIONS {
+                return Err(Error::InsufficientConfirmations);
+            }
+            let details = self.bets.get(bet_id).ok_or(Error::BetNotFound)?;
+            if details.commitment.is_some() {
+                // fulfill_randomness has no way to collect the player's secret, so a
+                // committed bet could never be settled through this path.
+                return Err(Error::CommittedBetRequiresReveal);
+            }
+
+            let request_id = self.next_request_id;
+            self.next_request_id += 1;
+
+            let ready_at = self.env().block_number() + num_confirmations as BlockNumber;
+            self.pending_requests
+                .insert(request_id, &PendingRequest { bet_id, ready_at });
+
+            Ok(request_id)
+        }
+
+        /// Permissioned callback invoked by the oracle once a requested number of
+        /// confirmations has elapsed; settles the corresponding bet in the same
+        /// transaction.
+        ///
+        /// `proof` must verify against the oracle's public key for `randomness` to
+        /// be trusted, the same as in [`Self::resolve_bet`]; this is what stops a
+        /// compromised oracle key from settling the bet with forged randomness.
+        #[ink(message)]
+        pub fn fulfill_randomness(
+            &mut self,
+            request_id: RequestId,
+            randomness: Vec<u8>,
+            proof: Vec<u8>,
+        ) -> Result<(), Error> {
+            if self.env().caller() != *self.oracle.as_ref() {
+                return Err(Error::Unauthorized);
+            }
+
+            let request = self
+                .pending_requests
+                .get(request_id)
+                .ok_or(Error::RequestNotFound)?;
+            if self.env().block_number() < request.ready_at {
+                return Err(Error::BetResolutionTooEarly);
+            }
+
+            let details = self.bets.get(request.bet_id).ok_or(Error::BetNotFound)?;
+            if !self.verify_proof(details.round, &randomness, &proof) {
+                return Err(Error::InvalidProof);
+            }
+            if details.commitment.is_some() {
+                // Committed bets carry a player-held secret that only resolve_bet
+                // can be given; the keeper-driven callback has no way to supply it.
+                return Err(Error::CommittedBetRequiresReveal);
+            }
+            self.settle_bet(&details, &randomness)?;
+
+            self.bets.remove(request.bet_id);
+            self.pending_requests.remove(request_id);
+
+            Ok(())
+        }
+
+        /// Creates a prediction market with `outcomes` mutually exclusive outcomes,
+        /// resolved from the oracle randomness at `resolution_round`. The creator
+        /// earns `rake_bps` (out of [`MAX_RAKE_BPS`]) of the total pool once resolved.
+        ///
+        /// `resolution_round` must be far enough ahead of the oracle's latest round
+        /// that its randomness isn't already known, the same way `register_bet`
+        /// pins its own resolution round; otherwise the creator could rig the
+        /// market against anyone staking against them.
+        #[ink(message)]
+        pub fn create_market(
+            &mut self,
+            resolution_round: Round,
+            outcomes: u8,
+            rake_bps: u16,
+        ) -> Result<MarketId, Error> {
+            if outcomes < 2 || rake_bps > MAX_RAKE_BPS {
+                return Err(Error::InvalidMarketParameters);
+            }
+            if resolution_round < self.oracle.get_latest_round() + 2 {
+                return Err(Error::InvalidMarketParameters);
+            }
+
+            let market_id = self.next_market_id;
+            self.next_market_id += 1;
+
+            let market = Market {
+                creator: self.env().caller(),
+                resolution_round,
+                outcomes,
+                rake_bps,
+                total_pool: 0,
+                winning_outcome: None,
+                rake_claimed: false,
+            };
+            self.markets.insert(market_id, &market);
+
+            Ok(market_id)
+        }
+
+        /// Stakes the transferred value on `outcome` of `market_id`.
+        ///
+        /// Rejected once `resolution_round`'s randomness is knowable (i.e. the
+        /// oracle has reached that round), the same way `create_market` pins a
+        /// round that isn't yet knowable; otherwise a staker could read the
+        /// winning outcome off-chain before `resolve_market` is called and snipe
+        /// the pool.
+        #[ink(message, payable)]
+        pub fn place_bet(&mut self, market_id: MarketId, outcome: u8) -> Result<(), Error> {
+            let mut market = self.markets.get(market_id).ok_or(Error::MarketNotFound)?;
+            if market.winning_outcome.is_some() {
+                return Err(Error::MarketAlreadyResolved);
+            }
+            if outcome >= market.outcomes {
+                return Err(Error::InvalidOutcome);
+            }
+            if self.oracle.get_latest_round() >= market.resolution_round {
+                return Err(Error::BettingClosed);
+            }
+
+            let caller = self.env().caller();
+            let stake = self.env().transferred_value();
+
+            let outcome_pool = self.outcome_pools.get((market_id, outcome)).unwrap_or(0);
+            self.outcome_pools
+                .insert((market_id, outcome), &(outcome_pool + stake));
+
+            let user_stake = self
+                .user_stakes
+                .get((market_id, caller, outcome))
+                .unwrap_or(0);
+            self.user_stakes
+                .insert((market_id, caller, outcome), &(user_stake + stake));
+
+            market.total_pool += stake;
+            self.markets.insert(market_id, &market);
+
+            Ok(())
+        }
+
+        /// Picks the winning outcome for `market_id` from the oracle's randomness
+        /// for `resolution_round`, reduced to the outcome space.
+        ///
+        /// As with [`Self::resolve_bet`], the randomness is only trusted once its
+        /// VRF proof verifies against the oracle's public key.
+        #[ink(message)]
+        pub fn resolve_market(&mut self, market_id: MarketId) -> Result<(), Error> {
+            let mut market = self.markets.get(market_id).ok_or(Error::MarketNotFound)?;
+            if market.winning_outcome.is_some() {
+                return Err(Error::MarketAlreadyResolved);
+            }
+
+            let (randomness, proof) = self
+                .get_random_with_proof(market.resolution_round)
+                .ok_or(Error::MarketResolutionTooEarly)?;
+            if !self.verify_proof(market.resolution_round, &randomness, &proof) {
+                return Err(Error::InvalidProof);
+            }
+            let winning_outcome = reduce_to_range(&randomness, market.outcomes as u64) as u8;
+
+            market.winning_outcome = Some(winning_outcome);
+            self.markets.insert(market_id, &market);
+
+            Ok(())
+        }
+
+        /// Pays the caller their pro-rata share of the total pool for
+        /// `market_id`, net of the creator's rake, based on their stake's
+        /// fraction of all stakes on the winning outcome.
+        #[ink(message)]
+        pub fn claim(&mut self, market_id: MarketId) -> Result<(), Error> {
+            let market = self.markets.get(market_id).ok_or(Error::MarketNotFound)?;
+            let winning_outcome = market.winning_outcome.ok_or(Error::MarketNotResolved)?;
+
+            let caller = self.env().caller();
+            let stake = self
+                .user_stakes
+                .get((market_id, caller, winning_outcome))
+                .unwrap_or(0);
+            if stake == 0 {
+                return Err(Error::NothingToClaim);
+            }
+
+            let winning_pool = self
+                .outcome_pools
+                .get((market_id, winning_outcome))
+                .unwrap_or(0);
+            let rake = market.total_pool * market.rake_bps as Balance / MAX_RAKE_BPS as Balance;
+            let payout_pool = market.total_pool - rake;
+            let payout = payout_pool * stake / winning_pool;
+
+            self.user_stakes.remove((market_id, caller, winning_outcome));
+            self.pay_reward(caller, payout)
+        }
+
+        /// Pays the market creator their `rake_bps` share of the total pool, once.
+        #[ink(message)]
+        pub fn claim_rake(&mut self, market_id: MarketId) -> Result<(), Error> {
+            let mut market = self.markets.get(market_id).ok_or(Error::MarketNotFound)?;
+            if market.winning_outcome.is_none() {
+                return Err(Error::MarketNotResolved);
+            }
+            if self.env().caller() != market.creator {
+                return Err(Error::Unauthorized);
+            }
+            if market.rake_claimed {
+                return Err(Error::RakeAlreadyClaimed);
+            }
+
+            let rake = market.total_pool * market.rake_bps as Balance / MAX_RAKE_BPS as Balance;
+            market.rake_claimed = true;
+            self.markets.insert(market_id, &market);
+
+            self.pay_reward(market.creator, rake)
+        }
+
+        /// Verifies a revealed `secret` against `details.commitment`, if any, and
+        /// mixes it with the oracle's `randomness` and `bet_id` so that neither the
+        /// oracle nor the player alone can predict or grind the final outcome.
+        fn reveal_randomness(
+            &self,
+            bet_id: BetId,
+            details: &BetDetails,
+            secret: Option<Vec<u8>>,
+            randomness: Vec<u8>,
+        ) -> Result<Vec<u8>, Error> {
+            let Some(commitment) = details.commitment else {
+                return Ok(randomness);
+            };
+
+            let secret = secret.ok_or(Error::MissingSecret)?;
+            if hash_secret(&secret) != commitment {
+                return Err(Error::CommitmentMismatch);
+            }
+
+            let mut input = Vec::with_capacity(randomness.len() + secret.len() + 16);
+            input.extend_from_slice(&randomness);
+            input.extend_from_slice(&secret);
+            input.extend_from_slice(&bet_id.to_be_bytes());
+
+            let mut output = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&input, &mut output);
+            Ok(output.to_vec())
+        }
+
+        /// Pays out `details`'s reward, if any, based on `randomness`.
+        fn settle_bet(&mut self, details: &BetDetails, randomness: &[u8]) -> Result<(), Error> {
+            let r = reduce_to_range(randomness, 101) as u8;
+            // The player loses (and forfeits the stake) if `n > r`, wins otherwise.
+            if details.n <= r {
+                let reward = details.stake * details.n as Balance / 100;
+                self.pay_reward(details.user, details.stake + reward)?;
+            }
+            Ok(())
+        }
+
+        fn get_id(&mut self) -> BetId {
+            let bet_id = self.next_bet_id;
+            self.next_bet_id += 1;
+            bet_id
         }
 
         fn pay_fee(&self, user: User) -> Result<(), Error> {
@@ -127,14 +607,431 @@ mod casino {
             Ok(())
         }
 
-        fn is_victorious(&self, randomness: Vec<u8>) -> bool {
-            // implement victory logics
-            true
+        fn pay_reward(&self, user: User, amount: Balance) -> Result<(), Error> {
+            self.env()
+                .transfer(user, amount)
+                .map_err(|_| Error::FailedTransfer)
         }
+    }
 
-        fn pay_reward(&self, user: User) -> Result<(), Error> {
-            //implement reward payment
-            Ok(())
+    /// Reduces `randomness` to a uniform value in `[0, bound)` using rejection sampling.
+    ///
+    /// A naive `seed % bound` is biased whenever `bound` does not evenly divide
+    /// `2^64` (the number of `u64` values), since the remainder classes
+    /// `0..=u64::MAX % bound` occur one extra time. Instead, we only accept seeds below the largest multiple of
+    /// `bound` that fits in a `u64` (`limit`), and re-derive the seed by hashing it
+    /// together with a counter until it falls in the unbiased range.
+    fn reduce_to_range(randomness: &[u8], bound: u64) -> u64 {
+        let limit = u64::MAX - (u64::MAX % bound);
+
+        let mut seed = be_u64(randomness);
+        let mut counter: u32 = 0;
+        while seed >= limit {
+            let mut input = Vec::with_capacity(randomness.len() + 4);
+            input.extend_from_slice(randomness);
+            input.extend_from_slice(&counter.to_be_bytes());
+
+            let mut output = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&input, &mut output);
+            seed = be_u64(&output);
+            counter += 1;
+        }
+
+        seed % bound
+    }
+
+    /// Hashes a commit-reveal `secret` the same way a committing user is expected
+    /// to when computing `commitment = hash(secret)` off-chain.
+    fn hash_secret(secret: &[u8]) -> Hash {
+        let mut output = [0u8; 32];
+        ink::env::hash_bytes::<ink::env::hash::Blake2x256>(secret, &mut output);
+        Hash::from(output)
+    }
+
+    /// Interprets the first 8 bytes of `bytes` as a big-endian `u64`, zero-padding
+    /// if fewer than 8 bytes are available.
+    fn be_u64(bytes: &[u8]) -> u64 {
+        let mut buf = [0u8; 8];
+        let len = bytes.len().min(8);
+        buf[..len].copy_from_slice(&bytes[..len]);
+        u64::from_be_bytes(buf)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        fn new_casino() -> Casino {
+            Casino::new(default_accounts().bob, [0u8; 33])
+        }
+
+        fn bet(n: u8, stake: Balance) -> BetDetails {
+            BetDetails {
+                round: 1,
+                user: default_accounts().alice,
+                n,
+                stake,
+                commitment: None,
+            }
+        }
+
+        // `randomness` whose first 8 bytes are the big-endian encoding of `seed`,
+        // chosen well below `reduce_to_range`'s rejection limit so `r == seed % 101`.
+        fn randomness_for_r(seed: u64) -> [u8; 8] {
+            seed.to_be_bytes()
+        }
+
+        #[ink::test]
+        fn settle_bet_zero_always_wins_with_no_reward() {
+            let mut casino = new_casino();
+            let details = bet(0, 100);
+            casino.settle_bet(&details, &randomness_for_r(50)).unwrap();
+
+            let transfers = ink::env::test::recorded_transfers::<ink::env::DefaultEnvironment>();
+            assert_eq!(transfers.len(), 1);
+            assert_eq!(transfers[0].value, 100);
+        }
+
+        #[ink::test]
+        fn settle_bet_hundred_wins_only_on_r_hundred_and_doubles_stake() {
+            let mut casino = new_casino();
+            let details = bet(100, 100);
+            casino.settle_bet(&details, &randomness_for_r(100)).unwrap();
+
+            let transfers = ink::env::test::recorded_transfers::<ink::env::DefaultEnvironment>();
+            assert_eq!(transfers.len(), 1);
+            assert_eq!(transfers[0].value, 200);
+        }
+
+        #[ink::test]
+        fn settle_bet_hundred_loses_below_r_hundred() {
+            let mut casino = new_casino();
+            let details = bet(100, 100);
+            casino.settle_bet(&details, &randomness_for_r(99)).unwrap();
+
+            let transfers = ink::env::test::recorded_transfers::<ink::env::DefaultEnvironment>();
+            assert!(transfers.is_empty());
+        }
+
+        #[ink::test]
+        fn settle_bet_pays_proportional_reward_between_the_extremes() {
+            let mut casino = new_casino();
+            let details = bet(40, 100);
+            // n = 40 <= r = 60, so the bet wins; reward = 100 * 40 / 100 = 40.
+            casino.settle_bet(&details, &randomness_for_r(60)).unwrap();
+
+            let transfers = ink::env::test::recorded_transfers::<ink::env::DefaultEnvironment>();
+            assert_eq!(transfers.len(), 1);
+            assert_eq!(transfers[0].value, 140);
+        }
+
+        #[test]
+        fn reduce_to_range_always_stays_in_bound() {
+            for bound in [1u64, 2, 7, 101, 1_000_000] {
+                for randomness in [&[0u8; 32][..], &[0xFFu8; 32][..], &randomness_for_r(12345)] {
+                    assert!(reduce_to_range(randomness, bound) < bound);
+                }
+            }
+        }
+
+        #[test]
+        fn hash_secret_is_deterministic_and_input_sensitive() {
+            assert_eq!(hash_secret(b"shh"), hash_secret(b"shh"));
+            assert_ne!(hash_secret(b"shh"), hash_secret(b"shhh"));
+        }
+
+        #[ink::test]
+        fn verify_proof_rejects_malformed_proof() {
+            let casino = new_casino();
+            assert!(!casino.verify_proof(1, &randomness_for_r(1), &[0u8; 10]));
+        }
+
+        #[ink::test]
+        fn verify_proof_rejects_a_well_formed_garbage_signature() {
+            let casino = new_casino();
+            // A well-formed but garbage signature must not recover to the stored
+            // (all-zero) oracle public key.
+            assert!(!casino.verify_proof(1, &randomness_for_r(1), &[0u8; 65]));
+        }
+
+        #[ink::test]
+        fn register_bet_rejects_a_number_above_the_bound() {
+            let mut casino = new_casino();
+            assert_eq!(
+                casino.register_bet(101, None),
+                Err(Error::InvalidBetNumber)
+            );
+        }
+
+        #[ink::test]
+        fn reveal_randomness_passes_through_uncommitted_bets() {
+            let casino = new_casino();
+            let details = bet(10, 100);
+            let randomness = randomness_for_r(1).to_vec();
+            assert_eq!(
+                casino.reveal_randomness(0, &details, None, randomness.clone()),
+                Ok(randomness)
+            );
+        }
+
+        #[ink::test]
+        fn reveal_randomness_requires_the_secret_for_a_committed_bet() {
+            let casino = new_casino();
+            let mut details = bet(10, 100);
+            details.commitment = Some(hash_secret(b"shh"));
+            assert_eq!(
+                casino.reveal_randomness(0, &details, None, randomness_for_r(1).to_vec()),
+                Err(Error::MissingSecret)
+            );
+        }
+
+        #[ink::test]
+        fn reveal_randomness_rejects_a_secret_that_does_not_match_the_commitment() {
+            let casino = new_casino();
+            let mut details = bet(10, 100);
+            details.commitment = Some(hash_secret(b"shh"));
+            assert_eq!(
+                casino.reveal_randomness(
+                    0,
+                    &details,
+                    Some(b"wrong".to_vec()),
+                    randomness_for_r(1).to_vec()
+                ),
+                Err(Error::CommitmentMismatch)
+            );
+        }
+
+        #[ink::test]
+        fn reveal_randomness_mixes_in_the_revealed_secret() {
+            let casino = new_casino();
+            let mut details = bet(10, 100);
+            details.commitment = Some(hash_secret(b"shh"));
+            let randomness = randomness_for_r(1).to_vec();
+
+            let mixed = casino
+                .reveal_randomness(0, &details, Some(b"shh".to_vec()), randomness.clone())
+                .unwrap();
+
+            // The mixed output must depend on the secret, not just pass through.
+            assert_ne!(mixed, randomness);
+            // And it must be deterministic given the same inputs.
+            assert_eq!(
+                mixed,
+                casino
+                    .reveal_randomness(0, &details, Some(b"shh".to_vec()), randomness)
+                    .unwrap()
+            );
+        }
+
+        #[ink::test]
+        fn request_randomness_rejects_too_few_confirmations() {
+            let mut casino = new_casino();
+            casino.bets.insert(0, &bet(10, 100));
+            assert_eq!(
+                casino.request_randomness(0, 0, 0),
+                Err(Error::InsufficientConfirmations)
+            );
+        }
+
+        #[ink::test]
+        fn request_randomness_rejects_an_unknown_bet() {
+            let mut casino = new_casino();
+            assert_eq!(
+                casino.request_randomness(0, 1, 0),
+                Err(Error::BetNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn request_randomness_rejects_a_committed_bet() {
+            let mut casino = new_casino();
+            let mut details = bet(10, 100);
+            details.commitment = Some(hash_secret(b"shh"));
+            casino.bets.insert(0, &details);
+            assert_eq!(
+                casino.request_randomness(0, 1, 0),
+                Err(Error::CommittedBetRequiresReveal)
+            );
+        }
+
+        #[ink::test]
+        fn fulfill_randomness_rejects_a_non_oracle_caller() {
+            let mut casino = new_casino();
+            casino.bets.insert(0, &bet(10, 100));
+            let request_id = casino.request_randomness(0, 1, 0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(
+                default_accounts().alice,
+            );
+            assert_eq!(
+                casino.fulfill_randomness(request_id, randomness_for_r(50).to_vec(), Vec::new()),
+                Err(Error::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn fulfill_randomness_rejects_an_unknown_request() {
+            let mut casino = new_casino();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(default_accounts().bob);
+            assert_eq!(
+                casino.fulfill_randomness(0, randomness_for_r(50).to_vec(), Vec::new()),
+                Err(Error::RequestNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn fulfill_randomness_rejects_before_enough_confirmations() {
+            let mut casino = new_casino();
+            casino.bets.insert(0, &bet(10, 100));
+            let request_id = casino.request_randomness(0, 10, 0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(default_accounts().bob);
+            assert_eq!(
+                casino.fulfill_randomness(request_id, randomness_for_r(50).to_vec(), Vec::new()),
+                Err(Error::BetResolutionTooEarly)
+            );
+        }
+
+        #[ink::test]
+        fn fulfill_randomness_rejects_an_invalid_proof() {
+            let mut casino = new_casino();
+            casino.bets.insert(0, &bet(10, 100));
+            let request_id = casino.request_randomness(0, 1, 0).unwrap();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(default_accounts().bob);
+            assert_eq!(
+                casino.fulfill_randomness(request_id, randomness_for_r(50).to_vec(), Vec::new()),
+                Err(Error::InvalidProof)
+            );
+        }
+
+        fn market(
+            outcomes: u8,
+            rake_bps: u16,
+            total_pool: Balance,
+            winning_outcome: Option<u8>,
+        ) -> Market {
+            Market {
+                creator: default_accounts().charlie,
+                resolution_round: 10,
+                outcomes,
+                rake_bps,
+                total_pool,
+                winning_outcome,
+                rake_claimed: false,
+            }
+        }
+
+        #[ink::test]
+        fn create_market_rejects_a_single_outcome() {
+            let mut casino = new_casino();
+            assert_eq!(
+                casino.create_market(100, 1, 0),
+                Err(Error::InvalidMarketParameters)
+            );
+        }
+
+        #[ink::test]
+        fn create_market_rejects_a_rake_above_the_bound() {
+            let mut casino = new_casino();
+            assert_eq!(
+                casino.create_market(100, 2, MAX_RAKE_BPS + 1),
+                Err(Error::InvalidMarketParameters)
+            );
+        }
+
+        #[ink::test]
+        fn place_bet_rejects_an_unknown_market() {
+            let mut casino = new_casino();
+            assert_eq!(casino.place_bet(0, 0), Err(Error::MarketNotFound));
+        }
+
+        #[ink::test]
+        fn place_bet_rejects_an_out_of_range_outcome() {
+            let mut casino = new_casino();
+            casino.markets.insert(0, &market(2, 0, 0, None));
+            assert_eq!(casino.place_bet(0, 2), Err(Error::InvalidOutcome));
+        }
+
+        #[ink::test]
+        fn place_bet_rejects_an_already_resolved_market() {
+            let mut casino = new_casino();
+            casino.markets.insert(0, &market(2, 0, 0, Some(0)));
+            assert_eq!(casino.place_bet(0, 0), Err(Error::MarketAlreadyResolved));
+        }
+
+        #[ink::test]
+        fn resolve_market_rejects_an_unknown_market() {
+            let mut casino = new_casino();
+            assert_eq!(casino.resolve_market(0), Err(Error::MarketNotFound));
+        }
+
+        #[ink::test]
+        fn resolve_market_rejects_an_already_resolved_market() {
+            let mut casino = new_casino();
+            casino.markets.insert(0, &market(2, 0, 0, Some(0)));
+            assert_eq!(casino.resolve_market(0), Err(Error::MarketAlreadyResolved));
+        }
+
+        #[ink::test]
+        fn claim_pays_the_winner_their_pro_rata_share_net_of_the_rake() {
+            let mut casino = new_casino();
+            let alice = default_accounts().alice;
+            casino.markets.insert(0, &market(2, 1_000, 1_000, Some(1)));
+            casino.outcome_pools.insert((0, 1), &400);
+            casino.user_stakes.insert((0, alice, 1), &100);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            casino.claim(0).unwrap();
+
+            // rake = 1_000 * 1_000 / 10_000 = 100; payout_pool = 900; payout = 900 * 100 / 400 = 225.
+            let transfers = ink::env::test::recorded_transfers::<ink::env::DefaultEnvironment>();
+            assert_eq!(transfers.len(), 1);
+            assert_eq!(transfers[0].value, 225);
+        }
+
+        #[ink::test]
+        fn claim_rejects_a_caller_with_nothing_staked_on_the_winning_outcome() {
+            let mut casino = new_casino();
+            casino.markets.insert(0, &market(2, 0, 1_000, Some(1)));
+            assert_eq!(casino.claim(0), Err(Error::NothingToClaim));
+        }
+
+        #[ink::test]
+        fn claim_rejects_an_unresolved_market() {
+            let mut casino = new_casino();
+            casino.markets.insert(0, &market(2, 0, 1_000, None));
+            assert_eq!(casino.claim(0), Err(Error::MarketNotResolved));
+        }
+
+        #[ink::test]
+        fn claim_rake_pays_the_creator_once() {
+            let mut casino = new_casino();
+            let charlie = default_accounts().charlie;
+            casino.markets.insert(0, &market(2, 1_000, 1_000, Some(1)));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            casino.claim_rake(0).unwrap();
+
+            let transfers = ink::env::test::recorded_transfers::<ink::env::DefaultEnvironment>();
+            assert_eq!(transfers.len(), 1);
+            assert_eq!(transfers[0].value, 100);
+
+            assert_eq!(casino.claim_rake(0), Err(Error::RakeAlreadyClaimed));
+        }
+
+        #[ink::test]
+        fn claim_rake_rejects_a_non_creator_caller() {
+            let mut casino = new_casino();
+            casino.markets.insert(0, &market(2, 1_000, 1_000, Some(1)));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(default_accounts().alice);
+            assert_eq!(casino.claim_rake(0), Err(Error::Unauthorized));
         }
     }
 }